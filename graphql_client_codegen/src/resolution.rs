@@ -2,8 +2,103 @@
 
 use crate::schema::FieldRef;
 use crate::schema::TypeRef;
-use crate::schema::{ObjectRef, Schema, StoredFieldId, TypeId};
-use std::collections::HashSet;
+use crate::schema::{InterfaceRef, ObjectRef, Schema, StoredFieldId, TypeId};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+use std::rc::Rc;
+
+/// An error produced while resolving a query against a schema, optionally carrying the source
+/// position of the offending node so it can be reported as "message at line:column" and, given
+/// the original document text, rendered with a caret under the exact spot.
+#[derive(Debug)]
+pub(crate) struct ResolutionError {
+    message: String,
+    position: Option<graphql_parser::Pos>,
+}
+
+impl ResolutionError {
+    fn new(message: impl Into<String>, position: graphql_parser::Pos) -> Self {
+        ResolutionError {
+            message: message.into(),
+            position: Some(position),
+        }
+    }
+
+    /// For violations that aren't anchored to a single node (e.g. a document-wide rule like "lone
+    /// anonymous operation"), where no single source position is the right one to point at.
+    fn without_position(message: impl Into<String>) -> Self {
+        ResolutionError {
+            message: message.into(),
+            position: None,
+        }
+    }
+
+    /// Renders the error together with the source line it points at and a caret under the
+    /// offending column, e.g.:
+    ///
+    /// ```text
+    /// No field named bio on User at 4:7
+    ///       bio
+    ///       ^
+    /// ```
+    pub(crate) fn render_with_snippet(&self, source: &str) -> String {
+        match self.position {
+            Some(position) => {
+                let line = source.lines().nth(position.line.saturating_sub(1)).unwrap_or("");
+                let caret = format!("{}^", " ".repeat(position.column.saturating_sub(1)));
+                format!("{}\n{}\n{}", self, line, caret)
+            }
+            None => self.to_string(),
+        }
+    }
+}
+
+impl std::fmt::Display for ResolutionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.position {
+            Some(position) => {
+                write!(f, "{} at {}:{}", self.message, position.line, position.column)
+            }
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+impl std::error::Error for ResolutionError {}
+
+/// The violations collected by a single [`validate`] pass. Unlike a pre-joined string, each
+/// [`ResolutionError`] keeps its own position, so a caller that wants a caret'd snippet for a
+/// specific violation can still get one via [`ResolutionError::render_with_snippet`] instead of
+/// only ever seeing the flattened [`Display`](std::fmt::Display) of the whole batch.
+#[derive(Debug)]
+pub(crate) struct ValidationErrors(Vec<ResolutionError>);
+
+impl ValidationErrors {
+    pub(crate) fn errors(&self) -> &[ResolutionError] {
+        &self.0
+    }
+}
+
+impl std::fmt::Display for ValidationErrors {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (index, error) in self.0.iter().enumerate() {
+            if index > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{}", error)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl std::error::Error for ValidationErrors {}
+
+/// The name given to an anonymous operation: the shorthand `{ ... }` form or a keyword form with
+/// no name, such as `query { ... }`. Per the GraphQL spec, a document may contain such an
+/// operation only if it is the document's sole operation.
+const ANONYMOUS_OPERATION_NAME: &str = "";
 
 pub(crate) fn resolve(
     schema: &Schema,
@@ -22,6 +117,9 @@ pub(crate) fn resolve(
         }
     }
 
+    validate(schema, &resolved_query)?;
+    intern_selections(schema, &mut resolved_query);
+
     Ok(resolved_query)
 }
 
@@ -31,7 +129,12 @@ fn resolve_fragment(
     fragment: &graphql_parser::query::FragmentDefinition,
 ) -> anyhow::Result<()> {
     let graphql_parser::query::TypeCondition::On(on) = &fragment.type_condition;
-    let on = schema.find_type(on).expect("TODO: proper error message");
+    let on = schema.find_type(on).ok_or_else(|| {
+        ResolutionError::new(
+            format!("Unknown type `{}` in fragment type condition", on),
+            fragment.position,
+        )
+    })?;
     let resolved_fragment = ResolvedFragment {
         name: fragment.name.clone(),
         on,
@@ -54,15 +157,23 @@ fn resolve_object_selection(
             match item {
                 graphql_parser::query::Selection::Field(field) => {
                     let field_ref = object.get_field_by_name(&field.name).ok_or_else(|| {
-                        anyhow::anyhow!("No field named {} on {}", &field.name, object.name())
+                        unknown_field_error(
+                            &field.name,
+                            object.name(),
+                            object.fields().map(|field| field.name()),
+                            field.position,
+                        )
                     })?;
                     Ok(IdSelection::Field(
                         field_ref.id(),
-                        resolve_selection(
+                        field.alias.clone(),
+                        field.arguments.clone(),
+                        Rc::from(resolve_selection(
                             object.schema(),
                             field_ref.type_id(),
                             &field.selection_set,
-                        )?,
+                        )?),
+                        field.position,
                     ))
                 }
                 graphql_parser::query::Selection::InlineFragment(inline) => {
@@ -78,6 +189,111 @@ fn resolve_object_selection(
     Ok(id_selection)
 }
 
+fn resolve_interface_selection(
+    interface: InterfaceRef<'_>,
+    selection_set: &graphql_parser::query::SelectionSet,
+) -> anyhow::Result<Vec<IdSelection>> {
+    let id_selection: Vec<IdSelection> = selection_set
+        .items
+        .iter()
+        .map(|item| -> anyhow::Result<_> {
+            match item {
+                graphql_parser::query::Selection::Field(field) => {
+                    let field_ref = interface.get_field_by_name(&field.name).ok_or_else(|| {
+                        unknown_field_error(
+                            &field.name,
+                            interface.name(),
+                            interface.fields().map(|field| field.name()),
+                            field.position,
+                        )
+                    })?;
+                    Ok(IdSelection::Field(
+                        field_ref.id(),
+                        field.alias.clone(),
+                        field.arguments.clone(),
+                        Rc::from(resolve_selection(
+                            interface.schema(),
+                            field_ref.type_id(),
+                            &field.selection_set,
+                        )?),
+                        field.position,
+                    ))
+                }
+                graphql_parser::query::Selection::InlineFragment(inline) => {
+                    resolve_interface_inline_fragment(interface, inline)
+                }
+                graphql_parser::query::Selection::FragmentSpread(fragment_spread) => Ok(
+                    IdSelection::FragmentSpread(fragment_spread.fragment_name.clone()),
+                ),
+            }
+        })
+        .collect::<Result<_, _>>()?;
+
+    Ok(id_selection)
+}
+
+fn resolve_interface_inline_fragment(
+    interface: InterfaceRef<'_>,
+    inline_fragment: &graphql_parser::query::InlineFragment,
+) -> anyhow::Result<IdSelection> {
+    let graphql_parser::query::TypeCondition::On(on) = inline_fragment.type_condition.as_ref().ok_or_else(|| {
+        ResolutionError::new("Inline fragment is missing a type condition", inline_fragment.position)
+    })?;
+    let type_id = interface.schema().find_type(on).ok_or_else(|| {
+        ResolutionError::new(
+            format!("Unknown type `{}` in inline fragment type condition", on),
+            inline_fragment.position,
+        )
+    })?;
+
+    if !is_valid_interface_type_condition(interface, type_id) {
+        return Err(ResolutionError::new(
+            format!(
+                "Type `{}` does not implement interface `{}`",
+                on,
+                interface.name()
+            ),
+            inline_fragment.position,
+        )
+        .into());
+    }
+
+    Ok(IdSelection::InlineFragment(
+        type_id,
+        Rc::from(resolve_selection(
+            interface.schema(),
+            type_id,
+            &inline_fragment.selection_set,
+        )?),
+        inline_fragment.position,
+    ))
+}
+
+/// A type condition on an interface-typed field is valid if it names the interface itself, a
+/// concrete type implementing it, or another interface whose implementors overlap with it — the
+/// condition just needs to be satisfiable by at least one of the interface's possible runtime
+/// types. This matches the GraphQL spec, which allows fragment conditions on the abstract type
+/// itself, not only on its concrete implementors.
+fn is_valid_interface_type_condition(interface: InterfaceRef<'_>, type_id: TypeId) -> bool {
+    if type_id == interface.type_id() {
+        return true;
+    }
+
+    match type_id {
+        TypeId::Interface(other_id) => {
+            let other = interface.schema().interface(other_id);
+            interface.implementors().any(|implementor| {
+                other
+                    .implementors()
+                    .any(|other_implementor| other_implementor.type_id() == implementor.type_id())
+            })
+        }
+        _ => interface
+            .implementors()
+            .any(|implementor| implementor.type_id() == type_id),
+    }
+}
+
 fn resolve_selection(
     schema: &Schema,
     on: TypeId,
@@ -90,7 +306,7 @@ fn resolve_selection(
         }
         TypeId::Interface(interface_id) => {
             let interface = schema.interface(interface_id);
-            todo!("interface thing")
+            resolve_interface_selection(interface, selection_set)
         }
         other => {
             anyhow::ensure!(
@@ -107,19 +323,165 @@ fn resolve_inline_fragment(
     schema: &Schema,
     inline_fragment: &graphql_parser::query::InlineFragment,
 ) -> anyhow::Result<IdSelection> {
-    let graphql_parser::query::TypeCondition::On(on) = inline_fragment
-        .type_condition
-        .as_ref()
-        .expect("missing type condition");
-    let type_id = schema
-        .find_type(on)
-        .ok_or_else(|| anyhow::anyhow!("TODO: error message"))?;
+    let graphql_parser::query::TypeCondition::On(on) = inline_fragment.type_condition.as_ref().ok_or_else(|| {
+        ResolutionError::new("Inline fragment is missing a type condition", inline_fragment.position)
+    })?;
+    let type_id = schema.find_type(on).ok_or_else(|| {
+        ResolutionError::new(
+            format!("Unknown type `{}` in inline fragment type condition", on),
+            inline_fragment.position,
+        )
+    })?;
     Ok(IdSelection::InlineFragment(
         type_id,
-        resolve_selection(schema, type_id, &inline_fragment.selection_set)?,
+        Rc::from(resolve_selection(schema, type_id, &inline_fragment.selection_set)?),
+        inline_fragment.position,
     ))
 }
 
+fn resolve_variables(
+    schema: &Schema,
+    variable_definitions: &[graphql_parser::query::VariableDefinition],
+) -> anyhow::Result<Vec<ResolvedVariable>> {
+    variable_definitions
+        .iter()
+        .map(|variable_definition| {
+            let r#type = schema.resolve_input_type(&variable_definition.var_type)?;
+
+            Ok(ResolvedVariable {
+                name: variable_definition.name.clone(),
+                default: variable_definition.default_value.clone(),
+                r#type,
+                position: variable_definition.position,
+            })
+        })
+        .collect()
+}
+
+/// Checks that every `$variable` referenced in a field argument was declared on the operation,
+/// that a nullable variable without a default value does not fill a required argument, and that
+/// every declared variable is referenced at least once. The variable's declared named type and
+/// list wrappers are not compared with the argument's type.
+///
+/// Fragments don't own their own variables - a spread fragment's body is validated as if it were
+/// inlined into the spreading operation, using that operation's declared variables. So this
+/// expands every `FragmentSpread` it encounters into the target fragment's selection rather than
+/// treating it as a no-op, and is run once per operation from [`validate`] (after every fragment
+/// in the document has been resolved, since a fragment can be spread before its own definition is
+/// reached).
+fn validate_variable_usage(
+    schema: &Schema,
+    variables: &[ResolvedVariable],
+    selection: &[IdSelection],
+    fragments: &[ResolvedFragment],
+) -> Result<(), ResolutionError> {
+    let mut used_variables: HashSet<&str> = HashSet::new();
+
+    fn walk<'a>(
+        schema: &Schema,
+        variables: &'a [ResolvedVariable],
+        fragments: &'a [ResolvedFragment],
+        selection: &'a [IdSelection],
+        used_variables: &mut HashSet<&'a str>,
+        seen_fragments: &mut HashSet<&'a str>,
+    ) -> Result<(), ResolutionError> {
+        for item in selection {
+            match item {
+                IdSelection::Field(id, _alias, arguments, children, position) => {
+                    let field = schema.field(*id);
+
+                    for (argument_name, value) in arguments {
+                        if let graphql_parser::query::Value::Variable(variable_name) = value {
+                            let variable = variables
+                                .iter()
+                                .find(|variable| variable.name == *variable_name)
+                                .ok_or_else(|| {
+                                    ResolutionError::new(
+                                        format!(
+                                            "Variable `${}` is not declared on this operation",
+                                            variable_name
+                                        ),
+                                        *position,
+                                    )
+                                })?;
+
+                            used_variables.insert(variable.name.as_str());
+
+                            let argument = field.get_argument_by_name(argument_name).ok_or_else(|| {
+                                ResolutionError::new(
+                                    format!(
+                                        "No argument named `{}` on field `{}`",
+                                        argument_name,
+                                        field.name()
+                                    ),
+                                    *position,
+                                )
+                            })?;
+
+                            if argument.is_required()
+                                && !variable.r#type.is_non_null()
+                                && variable.default.is_none()
+                            {
+                                return Err(ResolutionError::new(
+                                    format!(
+                                        "Variable `${}` is used in a required position for argument `{}` but is nullable and has no default value",
+                                        variable_name, argument_name
+                                    ),
+                                    *position,
+                                ));
+                            }
+                        }
+                    }
+
+                    walk(schema, variables, fragments, children, used_variables, seen_fragments)?;
+                }
+                IdSelection::InlineFragment(_, children, _position) => {
+                    walk(schema, variables, fragments, children, used_variables, seen_fragments)?;
+                }
+                IdSelection::FragmentSpread(name) => {
+                    // A fragment cycle is a separate spec violation we don't enforce here; guard
+                    // against it so this walk can't recurse forever.
+                    if seen_fragments.insert(name.as_str()) {
+                        if let Some(fragment) = fragments.iter().find(|frag| frag.name == *name) {
+                            walk(
+                                schema,
+                                variables,
+                                fragments,
+                                &fragment.selection,
+                                used_variables,
+                                seen_fragments,
+                            )?;
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    let mut seen_fragments: HashSet<&str> = HashSet::new();
+    walk(
+        schema,
+        variables,
+        fragments,
+        selection,
+        &mut used_variables,
+        &mut seen_fragments,
+    )?;
+
+    for variable in variables {
+        if !used_variables.contains(variable.name.as_str()) {
+            return Err(ResolutionError::new(
+                format!("Variable `${}` is declared but never used", variable.name),
+                variable.position,
+            ));
+        }
+    }
+
+    Ok(())
+}
+
 fn resolve_operation(
     query: &mut ResolvedQuery,
     schema: &Schema,
@@ -128,38 +490,568 @@ fn resolve_operation(
     match operation {
         graphql_parser::query::OperationDefinition::Mutation(m) => {
             let on = schema.mutation_type();
+            let variables = resolve_variables(schema, &m.variable_definitions)?;
+            let selection = resolve_object_selection(on, &m.selection_set)?;
+
             let resolved_operation: ResolvedOperation = ResolvedOperation {
-                name: m.name.as_ref().expect("mutation without name").to_owned(),
+                name: operation_name(m.name.as_deref()),
                 operation_type: crate::operations::OperationType::Mutation,
-                variables: Vec::new(),
-                selection: resolve_object_selection(on, &m.selection_set)?,
+                variables,
+                selection,
             };
 
             query.operations.push(resolved_operation);
         }
         graphql_parser::query::OperationDefinition::Query(q) => {
             let on = schema.query_type();
+            let variables = resolve_variables(schema, &q.variable_definitions)?;
+            let selection = resolve_object_selection(on, &q.selection_set)?;
 
             let resolved_operation: ResolvedOperation = ResolvedOperation {
-                name: q.name.as_ref().expect("query without name").to_owned(),
+                name: operation_name(q.name.as_deref()),
                 operation_type: crate::operations::OperationType::Query,
-                variables: Vec::new(),
-                selection: resolve_object_selection(on, &q.selection_set)?,
+                variables,
+                selection,
             };
 
             query.operations.push(resolved_operation);
         }
-        graphql_parser::query::OperationDefinition::Subscription(_) => {
-            todo!("resolve subscription")
+        graphql_parser::query::OperationDefinition::Subscription(s) => {
+            let on = schema.subscription_type();
+            let variables = resolve_variables(schema, &s.variable_definitions)?;
+            let selection = resolve_object_selection(on, &s.selection_set)?;
+
+            let resolved_operation: ResolvedOperation = ResolvedOperation {
+                name: operation_name(s.name.as_deref()),
+                operation_type: crate::operations::OperationType::Subscription,
+                variables,
+                selection,
+            };
+
+            query.operations.push(resolved_operation);
         }
-        graphql_parser::query::OperationDefinition::SelectionSet(_) => {
-            unreachable!("unnamed queries are not supported")
+        graphql_parser::query::OperationDefinition::SelectionSet(selection_set) => {
+            let on = schema.query_type();
+            let selection = resolve_object_selection(on, selection_set)?;
+
+            let resolved_operation = ResolvedOperation {
+                name: operation_name(None),
+                operation_type: crate::operations::OperationType::Query,
+                variables: Vec::new(),
+                selection,
+            };
+
+            query.operations.push(resolved_operation);
         }
     }
 
     Ok(())
 }
 
+/// Resolves an operation's name as parsed by `graphql-parser`, treating a missing name as the
+/// anonymous operation. This covers both ways a document can omit an operation's name: the
+/// `{ ... }` shorthand (handled by the `OperationDefinition::SelectionSet` variant, which has no
+/// `name` field at all) and the keyword form with the name left out, e.g. `query { ... }`, which
+/// `graphql-parser` also parses with `name: None`.
+fn operation_name(name: Option<&str>) -> String {
+    name.unwrap_or(ANONYMOUS_OPERATION_NAME).to_owned()
+}
+
+/// Builds a "no field named X on type Y" error, appending a "did you mean Z?" suggestion when a
+/// sibling field name is close enough to be a likely typo.
+fn unknown_field_error<'a>(
+    field_name: &str,
+    type_name: &str,
+    candidates: impl Iterator<Item = &'a str>,
+    position: graphql_parser::Pos,
+) -> anyhow::Error {
+    let message = match suggest_name(field_name, candidates) {
+        Some(suggestion) => format!(
+            "No field named {} on {}. Did you mean `{}`?",
+            field_name, type_name, suggestion
+        ),
+        None => format!("No field named {} on {}", field_name, type_name),
+    };
+
+    ResolutionError::new(message, position).into()
+}
+
+/// Picks the candidate closest to `name` by edit distance, if any is close enough to plausibly be
+/// a typo (at most a third of the candidate's length, and no more than 3 edits).
+fn suggest_name<'a>(name: &str, candidates: impl Iterator<Item = &'a str>) -> Option<&'a str> {
+    candidates
+        .map(|candidate| (candidate, levenshtein_distance(name, candidate)))
+        .filter(|(candidate, distance)| *distance <= 3 && *distance * 3 <= candidate.len())
+        .min_by_key(|(_, distance)| *distance)
+        .map(|(candidate, _)| candidate)
+}
+
+fn levenshtein_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, a_char) in a.iter().enumerate() {
+        let mut previous_diagonal = row[0];
+        row[0] = i + 1;
+
+        for (j, b_char) in b.iter().enumerate() {
+            let above = row[j + 1];
+            let cost = if a_char == b_char { 0 } else { 1 };
+            // `deletion`, `insertion` and `substitution` costs.
+            let new_value = (previous_diagonal + cost).min(above + 1).min(row[j] + 1);
+            previous_diagonal = above;
+            row[j + 1] = new_value;
+        }
+    }
+
+    row[b.len()]
+}
+
+/// A dedicated validation pass run once the whole query has been resolved. Unlike the
+/// field-existence check in [`resolve_object_selection`]/[`resolve_interface_selection`], which
+/// must fail fast (there is no field id to build an [`IdSelection`] with), these rules gather
+/// every violation in a query before reporting, so a query with several mistakes explains all of
+/// them at once instead of stopping at the first. The exception is the variable-usage check, which
+/// stops at its first violation and so reports at most one per operation.
+fn validate(schema: &Schema, query: &ResolvedQuery) -> Result<(), ValidationErrors> {
+    let mut errors: Vec<ResolutionError> = Vec::new();
+
+    for operation in &query.operations {
+        validate_selection(schema, &operation.selection, &mut errors);
+
+        if let Err(err) = validate_variable_usage(
+            schema,
+            &operation.variables,
+            &operation.selection,
+            &query.fragments,
+        ) {
+            errors.push(err);
+        }
+
+        if matches!(
+            operation.operation_type,
+            crate::operations::OperationType::Subscription
+        ) {
+            validate_single_root_field(operation, &query.fragments, &mut errors);
+        }
+    }
+
+    for fragment in &query.fragments {
+        validate_selection(schema, &fragment.selection, &mut errors);
+    }
+
+    validate_lone_anonymous_operation(query, &mut errors);
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(ValidationErrors(errors))
+    }
+}
+
+/// Enforces the GraphQL "lone anonymous operation" rule: a document that defines an unnamed
+/// operation (the shorthand `{ ... }` or a keyword form such as `query { ... }`) must not define
+/// any other operation.
+fn validate_lone_anonymous_operation(query: &ResolvedQuery, errors: &mut Vec<ResolutionError>) {
+    let has_anonymous_operation = query
+        .operations
+        .iter()
+        .any(|operation| operation.name == ANONYMOUS_OPERATION_NAME);
+
+    if has_anonymous_operation && query.operations.len() > 1 {
+        errors.push(ResolutionError::without_position(
+            "This document mixes an anonymous operation with other operations; \
+             the anonymous operation must be the only operation in the document",
+        ));
+    }
+}
+
+/// Enforces the GraphQL "single root field" rule for subscriptions: the operation's selection set
+/// must contain exactly one field once fragment spreads are expanded.
+fn validate_single_root_field(
+    operation: &ResolvedOperation,
+    fragments: &[ResolvedFragment],
+    errors: &mut Vec<ResolutionError>,
+) {
+    let root_fields = expand_root_selection(&operation.selection, fragments);
+
+    if root_fields.len() != 1 {
+        errors.push(ResolutionError::without_position(format!(
+            "Subscription `{}` must select exactly one top-level field, but selects {}",
+            operation.name(),
+            root_fields.len()
+        )));
+    }
+}
+
+/// Expands `selection` per the GraphQL `CollectFields` algorithm: both fragment spreads and
+/// inline fragments are transparent and contribute their own selected fields to the root set,
+/// leaving only actual fields behind. A `seen_fragments` guard (mirroring the one in
+/// [`validate_variable_usage`]) stops two fragments that spread each other from recursing forever.
+fn expand_root_selection<'a>(
+    selection: &'a [IdSelection],
+    fragments: &'a [ResolvedFragment],
+) -> Vec<&'a IdSelection> {
+    let mut expanded = Vec::new();
+    let mut seen_fragments: HashSet<&str> = HashSet::new();
+
+    expand_root_selection_into(selection, fragments, &mut seen_fragments, &mut expanded);
+
+    expanded
+}
+
+fn expand_root_selection_into<'a>(
+    selection: &'a [IdSelection],
+    fragments: &'a [ResolvedFragment],
+    seen_fragments: &mut HashSet<&'a str>,
+    expanded: &mut Vec<&'a IdSelection>,
+) {
+    for item in selection {
+        match item {
+            IdSelection::FragmentSpread(name) => {
+                if seen_fragments.insert(name.as_str()) {
+                    if let Some(fragment) = fragments.iter().find(|fragment| fragment.name == *name)
+                    {
+                        expand_root_selection_into(
+                            &fragment.selection,
+                            fragments,
+                            seen_fragments,
+                            expanded,
+                        );
+                    }
+                }
+            }
+            IdSelection::InlineFragment(_, children, _position) => {
+                expand_root_selection_into(children, fragments, seen_fragments, expanded);
+            }
+            field @ IdSelection::Field(..) => expanded.push(field),
+        }
+    }
+}
+
+fn validate_selection(schema: &Schema, selection: &[IdSelection], errors: &mut Vec<ResolutionError>) {
+    for item in selection {
+        match item {
+            IdSelection::Field(id, _alias, arguments, children, position) => {
+                let field = schema.field(*id);
+
+                validate_known_argument_names(&field, arguments, *position, errors);
+                validate_required_arguments_present(&field, arguments, *position, errors);
+                validate_arguments_of_correct_type(schema, &field, arguments, *position, errors);
+
+                validate_selection(schema, children, errors);
+            }
+            IdSelection::InlineFragment(_, children, _position) => {
+                validate_selection(schema, children, errors);
+            }
+            IdSelection::FragmentSpread(_) => (),
+        }
+    }
+}
+
+fn validate_known_argument_names(
+    field: &FieldRef<'_>,
+    arguments: &[(String, graphql_parser::query::Value)],
+    position: graphql_parser::Pos,
+    errors: &mut Vec<ResolutionError>,
+) {
+    for (argument_name, _) in arguments {
+        if field.get_argument_by_name(argument_name).is_none() {
+            let message = match suggest_name(
+                argument_name,
+                field.arguments().map(|argument| argument.name()),
+            ) {
+                Some(suggestion) => format!(
+                    "Unknown argument `{}` on field `{}`. Did you mean `{}`?",
+                    argument_name,
+                    field.name(),
+                    suggestion
+                ),
+                None => format!(
+                    "Unknown argument `{}` on field `{}`",
+                    argument_name,
+                    field.name()
+                ),
+            };
+
+            errors.push(ResolutionError::new(message, position));
+        }
+    }
+}
+
+fn validate_required_arguments_present(
+    field: &FieldRef<'_>,
+    arguments: &[(String, graphql_parser::query::Value)],
+    position: graphql_parser::Pos,
+    errors: &mut Vec<ResolutionError>,
+) {
+    for argument in field.arguments() {
+        let is_present = arguments
+            .iter()
+            .any(|(argument_name, _)| argument_name == argument.name());
+
+        if argument.is_required() && !is_present {
+            errors.push(ResolutionError::new(
+                format!(
+                    "Required argument `{}` of field `{}` is not present",
+                    argument.name(),
+                    field.name()
+                ),
+                position,
+            ));
+        }
+    }
+}
+
+fn validate_arguments_of_correct_type(
+    schema: &Schema,
+    field: &FieldRef<'_>,
+    arguments: &[(String, graphql_parser::query::Value)],
+    position: graphql_parser::Pos,
+    errors: &mut Vec<ResolutionError>,
+) {
+    for (argument_name, value) in arguments {
+        // Variables are checked in `validate_variable_usage`; here we only check the shape of
+        // literal values.
+        if matches!(value, graphql_parser::query::Value::Variable(_)) {
+            continue;
+        }
+
+        if let Some(argument) = field.get_argument_by_name(argument_name) {
+            if !value_matches_type(schema, value, &argument.input_type()) {
+                errors.push(ResolutionError::new(
+                    format!(
+                        "Argument `{}` of field `{}` has the wrong type",
+                        argument_name,
+                        field.name()
+                    ),
+                    position,
+                ));
+            }
+        }
+    }
+}
+
+fn value_matches_type(
+    schema: &Schema,
+    value: &graphql_parser::query::Value,
+    expected: &crate::schema::StoredInputFieldType,
+) -> bool {
+    if matches!(value, graphql_parser::query::Value::Null) {
+        return !expected.is_non_null();
+    }
+
+    if expected.is_list() {
+        return match value {
+            graphql_parser::query::Value::List(items) => items
+                .iter()
+                .all(|item| value_matches_type(schema, item, &expected.inner_type())),
+            _ => false,
+        };
+    }
+
+    if let graphql_parser::query::Value::Object(fields) = value {
+        let input_object = schema.input_object(expected.type_id());
+
+        let known_fields_are_valid = fields.keys().all(|field_name| {
+            input_object
+                .get_field_by_name(field_name)
+                .map(|input_field| {
+                    value_matches_type(schema, &fields[field_name], &input_field.input_type())
+                })
+                .unwrap_or(false)
+        });
+
+        let required_fields_are_present = input_object.fields().all(|input_field| {
+            !input_field.input_type().is_non_null() || fields.contains_key(input_field.name())
+        });
+
+        return known_fields_are_valid && required_fields_are_present;
+    }
+
+    let type_name = schema.type_name(expected.type_id());
+
+    match value {
+        graphql_parser::query::Value::Int(_) => {
+            matches!(type_name, "Int" | "Float") || !is_builtin_scalar(type_name)
+        }
+        graphql_parser::query::Value::Float(_) => {
+            type_name == "Float" || !is_builtin_scalar(type_name)
+        }
+        graphql_parser::query::Value::String(_) => {
+            matches!(type_name, "String" | "ID") || !is_builtin_scalar(type_name)
+        }
+        graphql_parser::query::Value::Boolean(_) => {
+            type_name == "Boolean" || !is_builtin_scalar(type_name)
+        }
+        // A bare name can only be a member of an enum: built-in scalars and custom scalars are
+        // always written as strings, numbers, booleans or objects.
+        graphql_parser::query::Value::Enum(_) => schema.is_enum(expected.type_id()),
+        // `expected.is_list()` was already handled above, so a list literal here can only be a
+        // shape mismatch.
+        graphql_parser::query::Value::List(_) => false,
+        // Variables are checked in `validate_variable_usage`; a literal's shape has nothing more
+        // to say about them here.
+        graphql_parser::query::Value::Variable(_) => true,
+        graphql_parser::query::Value::Null | graphql_parser::query::Value::Object(_) => {
+            unreachable!("handled above")
+        }
+    }
+}
+
+/// The GraphQL built-in scalar names, which are the only ones whose literal representation is
+/// fixed by the spec. A custom scalar's literal shape is defined by its implementation, so any
+/// string, number or boolean literal is accepted for one.
+fn is_builtin_scalar(name: &str) -> bool {
+    matches!(name, "Int" | "Float" | "String" | "Boolean" | "ID")
+}
+
+/// Interns structurally-identical selection sets so that fields selecting the same shape of data
+/// share one `Rc<[IdSelection]>` allocation, letting codegen emit one Rust type for all of them
+/// instead of a duplicate per occurrence.
+///
+/// Two selections are only considered identical when they agree on target type, field ids (in
+/// order), aliases and argument literals, and nested selections recursively - so an aliased field
+/// or one called with different arguments never merges with a look-alike. A fragment spread is
+/// keyed by its expanded contents, so a spread and the equivalent inline fragment on the
+/// fragment's type condition intern to the same entry.
+struct SelectionInterner<'q> {
+    schema: &'q Schema,
+    fragments: &'q [ResolvedFragment],
+    arena: HashMap<u64, Vec<(String, Rc<[IdSelection]>)>>,
+}
+
+impl<'q> SelectionInterner<'q> {
+    fn new(schema: &'q Schema, fragments: &'q [ResolvedFragment]) -> Self {
+        SelectionInterner {
+            schema,
+            fragments,
+            arena: HashMap::new(),
+        }
+    }
+
+    fn dedupe_top_level(&mut self, selection: Vec<IdSelection>) -> Vec<IdSelection> {
+        selection
+            .into_iter()
+            .map(|item| self.intern_item(item))
+            .collect()
+    }
+
+    fn intern_item(&mut self, item: IdSelection) -> IdSelection {
+        match item {
+            IdSelection::Field(id, alias, arguments, children, position) => {
+                let on = self.schema.field(id).type_id();
+                let children = self.intern_children(on, children);
+                IdSelection::Field(id, alias, arguments, children, position)
+            }
+            IdSelection::InlineFragment(type_id, children, position) => {
+                let children = self.intern_children(type_id, children);
+                IdSelection::InlineFragment(type_id, children, position)
+            }
+            spread @ IdSelection::FragmentSpread(_) => spread,
+        }
+    }
+
+    fn intern_children(&mut self, on: TypeId, children: Rc<[IdSelection]>) -> Rc<[IdSelection]> {
+        let deduped: Vec<IdSelection> = children
+            .iter()
+            .map(|child| self.intern_item(child.clone()))
+            .collect();
+
+        let key = structural_key(self.fragments, on, &deduped);
+        let mut hasher = DefaultHasher::new();
+        key.hash(&mut hasher);
+        let hash = hasher.finish();
+
+        let bucket = self.arena.entry(hash).or_default();
+        if let Some((_, existing)) = bucket.iter().find(|(existing_key, _)| *existing_key == key) {
+            return Rc::clone(existing);
+        }
+
+        let interned: Rc<[IdSelection]> = Rc::from(deduped);
+        bucket.push((key, Rc::clone(&interned)));
+        interned
+    }
+}
+
+fn intern_selections(schema: &Schema, query: &mut ResolvedQuery) {
+    // A fragment's body can itself spread another fragment, so the interner needs every
+    // fragment's (not yet deduped) selection available up front to expand those spreads
+    // consistently - an empty fragments slice here would make a spread inside a fragment body key
+    // differently than the same content written out inline.
+    let fragments_snapshot = query.fragments.clone();
+    let mut fragment_interner = SelectionInterner::new(schema, &fragments_snapshot);
+    for fragment in &mut query.fragments {
+        let selection = std::mem::take(&mut fragment.selection);
+        fragment.selection = fragment_interner.dedupe_top_level(selection);
+    }
+
+    let fragments: &[ResolvedFragment] = &query.fragments;
+    let mut operation_interner = SelectionInterner::new(schema, fragments);
+    for operation in &mut query.operations {
+        let selection = std::mem::take(&mut operation.selection);
+        operation.selection = operation_interner.dedupe_top_level(selection);
+    }
+}
+
+/// Builds a string uniquely identifying the shape of `selection` for the purposes of interning:
+/// target type, then for every item its kind, field id/alias/arguments or inline fragment
+/// condition, and its nested selection recursively. Fragment spreads are expanded to the key of
+/// an inline fragment on the fragment's type condition, so that a spread and its inlined
+/// equivalent produce the same string.
+fn structural_key(fragments: &[ResolvedFragment], on: TypeId, selection: &[IdSelection]) -> String {
+    let mut key = format!("{:?}", on);
+    let mut seen_fragments: HashSet<&str> = HashSet::new();
+
+    for item in selection {
+        key.push('|');
+        append_item_key(fragments, item, &mut key, &mut seen_fragments);
+    }
+
+    key
+}
+
+/// `seen_fragments` holds the fragments being expanded on the current path: without it, two
+/// fragments that spread each other recurse forever here, since this path runs unconditionally
+/// for every query, mutation and fragment via `intern_selections`. A fragment is removed again
+/// once its expansion is done, so spreading the same fragment twice expands it both times.
+fn append_item_key<'a>(
+    fragments: &'a [ResolvedFragment],
+    item: &'a IdSelection,
+    key: &mut String,
+    seen_fragments: &mut HashSet<&'a str>,
+) {
+    match item {
+        IdSelection::Field(id, alias, arguments, children, _position) => {
+            key.push_str(&format!("F{:?}{:?}{:?}[", id, alias, arguments));
+            for child in children.iter() {
+                append_item_key(fragments, child, key, seen_fragments);
+            }
+            key.push(']');
+        }
+        IdSelection::InlineFragment(type_id, children, _position) => {
+            key.push_str(&format!("I{:?}[", type_id));
+            for child in children.iter() {
+                append_item_key(fragments, child, key, seen_fragments);
+            }
+            key.push(']');
+        }
+        IdSelection::FragmentSpread(name) => match fragments.iter().find(|f| f.name == *name) {
+            Some(fragment) if seen_fragments.insert(name.as_str()) => {
+                key.push_str(&format!("I{:?}[", fragment.on));
+                for child in &fragment.selection {
+                    append_item_key(fragments, child, key, seen_fragments);
+                }
+                key.push(']');
+                seen_fragments.remove(name.as_str());
+            }
+            Some(_) => key.push_str(&format!("S!{}", name)),
+            None => key.push_str(&format!("S?{}", name)),
+        },
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 struct ResolvedFragmentId(usize);
 
@@ -169,7 +1061,40 @@ pub(crate) struct ResolvedQuery {
     fragments: Vec<ResolvedFragment>,
 }
 
-#[derive(Debug)]
+impl ResolvedQuery {
+    /// Looks up a single operation to generate code for. When `name` is given, the operation with
+    /// that name is returned, or an error if none matches. When `name` is `None`, the document
+    /// must define exactly one operation (named or the anonymous shorthand).
+    pub(crate) fn select_operation<'a>(
+        &'a self,
+        schema: &'a Schema,
+        name: Option<&str>,
+    ) -> anyhow::Result<Operation<'a>> {
+        let operation_id = match name {
+            Some(name) => self
+                .operations
+                .iter()
+                .position(|operation| operation.name == name)
+                .ok_or_else(|| anyhow::anyhow!("No operation named `{}` in this document", name))?,
+            None => match self.operations.len() {
+                0 => anyhow::bail!("This document defines no operations"),
+                1 => 0,
+                len => anyhow::bail!(
+                    "This document defines {} operations; an operation name must be specified",
+                    len
+                ),
+            },
+        };
+
+        Ok(Operation {
+            operation_id,
+            schema,
+            query: self,
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
 struct ResolvedFragment {
     name: String,
     on: crate::schema::TypeId,
@@ -228,20 +1153,29 @@ struct ResolvedVariable {
     name: String,
     default: Option<graphql_parser::query::Value>,
     r#type: crate::schema::StoredInputFieldType,
+    position: graphql_parser::Pos,
 }
 
 #[derive(Debug, Clone)]
 enum IdSelection {
-    Field(StoredFieldId, Vec<IdSelection>),
+    Field(
+        StoredFieldId,
+        Option<String>,
+        Vec<(String, graphql_parser::query::Value)>,
+        Rc<[IdSelection]>,
+        graphql_parser::Pos,
+    ),
     FragmentSpread(String),
-    InlineFragment(TypeId, Vec<IdSelection>),
+    InlineFragment(TypeId, Rc<[IdSelection]>, graphql_parser::Pos),
 }
 
 impl IdSelection {
     fn upgrade<'a>(&self, schema: &'a Schema, query: &'a ResolvedQuery) -> Selection<'a> {
         match self {
-            IdSelection::Field(id, selection) => Selection::Field(
+            IdSelection::Field(id, alias, arguments, selection, _position) => Selection::Field(
                 schema.field(*id),
+                alias.clone(),
+                arguments.clone(),
                 selection
                     .iter()
                     .map(|selection| selection.upgrade(schema, query))
@@ -256,7 +1190,7 @@ impl IdSelection {
                 query,
                 schema,
             }),
-            IdSelection::InlineFragment(typeid, selection) => Selection::InlineFragment(
+            IdSelection::InlineFragment(typeid, selection, _position) => Selection::InlineFragment(
                 typeid.upgrade(schema),
                 selection
                     .iter()
@@ -269,7 +1203,12 @@ impl IdSelection {
 
 #[derive(Debug, Clone)]
 enum Selection<'a> {
-    Field(FieldRef<'a>, Vec<Selection<'a>>),
+    Field(
+        FieldRef<'a>,
+        Option<String>,
+        Vec<(String, graphql_parser::query::Value)>,
+        Vec<Selection<'a>>,
+    ),
     FragmentSpread(Fragment<'a>),
     InlineFragment(TypeRef<'a>, Vec<Selection<'a>>),
 }
@@ -277,7 +1216,7 @@ enum Selection<'a> {
 impl Selection<'_> {
     fn collect_used_types(&self, used_types: &mut HashSet<TypeId>) {
         match self {
-            Selection::Field(field, selection) => {
+            Selection::Field(field, _alias, _arguments, selection) => {
                 used_types.insert(field.type_id());
 
                 selection
@@ -316,4 +1255,144 @@ impl Fragment<'_> {
             .iter()
             .map(|selection| selection.upgrade(&self.schema, &self.query))
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn levenshtein_distance_identical_strings_is_zero() {
+        assert_eq!(levenshtein_distance("name", "name"), 0);
+    }
+
+    #[test]
+    fn levenshtein_distance_counts_single_edits() {
+        assert_eq!(levenshtein_distance("name", "names"), 1);
+        assert_eq!(levenshtein_distance("name", "nam"), 1);
+        assert_eq!(levenshtein_distance("name", "nime"), 1);
+    }
+
+    #[test]
+    fn suggest_name_picks_the_closest_plausible_typo() {
+        let candidates = ["name", "age", "email"];
+        assert_eq!(suggest_name("nam", candidates.iter().copied()), Some("name"));
+    }
+
+    #[test]
+    fn suggest_name_ignores_candidates_that_are_too_different() {
+        let candidates = ["completelyDifferentField"];
+        assert_eq!(suggest_name("name", candidates.iter().copied()), None);
+    }
+
+    #[test]
+    fn operation_name_treats_missing_name_as_anonymous() {
+        assert_eq!(operation_name(None), ANONYMOUS_OPERATION_NAME);
+        assert_eq!(operation_name(Some("GetUser")), "GetUser");
+    }
+
+    #[test]
+    fn is_builtin_scalar_recognizes_the_five_spec_scalars() {
+        assert!(is_builtin_scalar("Int"));
+        assert!(is_builtin_scalar("Float"));
+        assert!(is_builtin_scalar("String"));
+        assert!(is_builtin_scalar("Boolean"));
+        assert!(is_builtin_scalar("ID"));
+        assert!(!is_builtin_scalar("DateTime"));
+    }
+
+    fn pos(line: usize, column: usize) -> graphql_parser::Pos {
+        graphql_parser::Pos { line, column }
+    }
+
+    #[test]
+    fn resolution_error_display_includes_position() {
+        let error = ResolutionError::new("Unknown field `bio`", pos(4, 7));
+        assert_eq!(error.to_string(), "Unknown field `bio` at 4:7");
+    }
+
+    #[test]
+    fn resolution_error_without_position_omits_location() {
+        let error = ResolutionError::without_position("Document-wide violation");
+        assert_eq!(error.to_string(), "Document-wide violation");
+    }
+
+    #[test]
+    fn resolution_error_render_with_snippet_points_at_the_column() {
+        let source = "query {\n  bio\n}\n";
+        let error = ResolutionError::new("No field named bio on User", pos(2, 3));
+        let rendered = error.render_with_snippet(source);
+        assert_eq!(
+            rendered,
+            "No field named bio on User at 2:3\n  bio\n  ^"
+        );
+    }
+
+    #[test]
+    fn validation_errors_display_joins_each_error_on_its_own_line() {
+        let errors = ValidationErrors(vec![
+            ResolutionError::new("first problem", pos(1, 1)),
+            ResolutionError::new("second problem", pos(2, 1)),
+        ]);
+        assert_eq!(
+            errors.to_string(),
+            "first problem at 1:1\nsecond problem at 2:1"
+        );
+        assert_eq!(errors.errors().len(), 2);
+    }
+
+    /// Regression test for a fragment cycle (`fragment A { ...B }` / `fragment B { ...A }`):
+    /// `expand_root_selection` must terminate instead of recursing forever, and inline fragments
+    /// must be flattened into the root field count rather than counted as one opaque field.
+    #[test]
+    fn expand_root_selection_terminates_on_fragment_cycles_and_flattens_inline_fragments() {
+        let fragment_a = ResolvedFragment {
+            name: "A".to_owned(),
+            on: TypeId::Object(0),
+            selection: vec![IdSelection::FragmentSpread("B".to_owned())],
+        };
+        let fragment_b = ResolvedFragment {
+            name: "B".to_owned(),
+            on: TypeId::Object(0),
+            selection: vec![IdSelection::FragmentSpread("A".to_owned())],
+        };
+        let fragments = vec![fragment_a, fragment_b];
+
+        let selection = vec![IdSelection::InlineFragment(
+            TypeId::Object(0),
+            Rc::from(vec![IdSelection::FragmentSpread("A".to_owned())]),
+            pos(1, 1),
+        )];
+
+        // Must return (not stack overflow), and the cyclic spread contributes no root fields.
+        assert_eq!(expand_root_selection(&selection, &fragments).len(), 0);
+    }
+
+    /// Regression test for the same cycle reached through `structural_key`/`append_item_key`,
+    /// which runs on every `resolve()` call via `intern_selections`.
+    #[test]
+    fn append_item_key_terminates_on_fragment_cycles() {
+        let fragment_a = ResolvedFragment {
+            name: "A".to_owned(),
+            on: TypeId::Object(0),
+            selection: vec![IdSelection::FragmentSpread("B".to_owned())],
+        };
+        let fragment_b = ResolvedFragment {
+            name: "B".to_owned(),
+            on: TypeId::Object(0),
+            selection: vec![IdSelection::FragmentSpread("A".to_owned())],
+        };
+        let fragments = vec![fragment_a, fragment_b];
+
+        let mut key = String::new();
+        let mut seen_fragments = HashSet::new();
+        append_item_key(
+            &fragments,
+            &IdSelection::FragmentSpread("A".to_owned()),
+            &mut key,
+            &mut seen_fragments,
+        );
+
+        assert!(!key.is_empty());
+    }
 }
\ No newline at end of file